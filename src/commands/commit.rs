@@ -1,38 +1,77 @@
 use anyhow::{anyhow, Result};
-use cliclack::{confirm, input, log, spinner};
+use cliclack::{input, log, select};
 use console::style;
+use std::io::Write;
 use std::process::Command;
 
+use crate::config::Config;
 use crate::llm::{prompts, LlmClient};
 
-pub async fn run() -> Result<()> {
+pub async fn run(
+    config: Option<Config>,
+    profile: Option<String>,
+    role: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
     let diff = git(&["diff", "--cached"])?;
     if diff.trim().is_empty() {
         log::warning("No staged changes. Use 'git add' first.")?;
         return Ok(());
     }
 
-    let sp = spinner();
-    sp.start("Generating commit message...");
+    let templates = prompts::resolve(config.as_ref(), role.as_deref())?;
+    let llm = LlmClient::with_profile(config.as_ref(), profile.as_deref())?;
+    let diff = llm.prepare_diff(&diff).await?;
 
-    let llm = LlmClient::new()?;
-    let message = llm.chat(prompts::COMMIT, &diff).await?.trim().to_lowercase();
+    // In dry-run mode just emit the message to stdout so it can be piped.
+    if dry_run {
+        let message = llm.chat(&templates.commit, &diff).await?.trim().to_string();
+        println!("{}", message);
+        return Ok(());
+    }
+
+    // Messages the user rejected; fed back so regenerations differ.
+    let mut rejected: Vec<String> = Vec::new();
+
+    loop {
+        print!("{} ", style("Generating:").green());
+        std::io::stdout().flush().ok();
+        let user = super::avoid(&diff, &rejected);
+        let message = llm
+            .chat_stream(&templates.commit, &user, |delta| {
+                print!("{}", delta);
+                std::io::stdout().flush().ok();
+            })
+            .await?
+            .trim()
+            .to_string();
+        println!();
 
-    sp.stop(format!("{} {}", style("Generated:").green(), message));
+        let final_msg: String = input("Edit message")
+            .default_input(&message)
+            .interact()?;
 
-    let final_msg: String = input("Edit message")
-        .default_input(&message)
-        .interact()?;
+        let action = select("What would you like to do?")
+            .item("commit", "✅ Commit", "")
+            .item("regenerate", "🔄 Regenerate", "Ask for a different message")
+            .item("cancel", "✖ Cancel", "")
+            .interact()?;
 
-    if confirm("Commit?").initial_value(true).interact()? {
-        let status = Command::new("git")
-            .args(["commit", "-m", &final_msg])
-            .status()?;
+        match action {
+            "commit" => {
+                let status = Command::new("git")
+                    .args(["commit", "-m", &final_msg])
+                    .status()?;
 
-        if status.success() {
-            log::success("Committed!")?;
-        } else {
-            return Err(anyhow!("git commit failed"));
+                if status.success() {
+                    log::success("Committed!")?;
+                } else {
+                    return Err(anyhow!("git commit failed"));
+                }
+                break;
+            }
+            "regenerate" => rejected.push(final_msg),
+            _ => break,
         }
     }
 
@@ -2,8 +2,9 @@ use anyhow::Result;
 use cliclack::{intro, outro, select};
 
 use super::{commit, pr};
+use crate::config::Config;
 
-pub async fn run() -> Result<()> {
+pub async fn run(config: Option<Config>) -> Result<()> {
     intro("🧘 Git Relax")?;
 
     let action = select("What would you like to do?")
@@ -12,8 +13,8 @@ pub async fn run() -> Result<()> {
         .interact()?;
 
     match action {
-        "commit" => commit::run().await?,
-        "pr" => pr::run(None).await?,
+        "commit" => commit::run(config, None, None, false).await?,
+        "pr" => pr::run(config, None, None, None, false).await?,
         _ => {}
     }
 
@@ -0,0 +1,19 @@
+pub mod commit;
+pub mod interactive;
+pub mod pr;
+
+pub use commit as cm;
+
+/// Append rejected suggestions to the diff so successive regenerations steer
+/// away from what the user already turned down.
+pub fn avoid(diff: &str, rejected: &[String]) -> String {
+    if rejected.is_empty() {
+        return diff.to_string();
+    }
+    let mut user = diff.to_string();
+    user.push_str("\n\nAvoid producing something like:");
+    for msg in rejected {
+        user.push_str(&format!("\n- {}", msg));
+    }
+    user
+}
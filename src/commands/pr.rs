@@ -1,11 +1,19 @@
 use anyhow::{anyhow, Result};
-use cliclack::{confirm, log, spinner};
+use cliclack::{log, select, spinner};
 use console::style;
+use std::io::Write;
 use std::process::Command;
 
+use crate::config::Config;
 use crate::llm::{prompts, LlmClient};
 
-pub async fn run(base: Option<String>) -> Result<()> {
+pub async fn run(
+    config: Option<Config>,
+    base: Option<String>,
+    profile: Option<String>,
+    role: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
     let base = base.unwrap_or_else(|| "main".into());
     let diff = git(&["diff", &format!("{}..HEAD", base)])?;
 
@@ -14,57 +22,96 @@ pub async fn run(base: Option<String>) -> Result<()> {
         return Ok(());
     }
 
-    let sp = spinner();
-    sp.start("Generating PR...");
-
-    let llm = LlmClient::new()?;
-    let title = llm
-        .chat(prompts::PR_TITLE, &diff)
-        .await?
-        .trim()
-        .to_lowercase();
-    let body = llm.chat(prompts::PR_BODY, &diff).await?.trim().to_string();
-
-    sp.stop(format!("{}", style("PR generated!").green()));
-
-    println!("\n{} {}\n", style("Title:").cyan().bold(), title);
-    println!("{}", style(&body).dim());
-    println!();
-
-    if confirm("Create PR?").initial_value(true).interact()? {
-        if !has_upstream() {
-            let sp = spinner();
-            sp.start("Pushing to remote...");
-            let status = Command::new("git")
-                .args(["push", "-u", "origin", "HEAD"])
-                .status()?;
-            if !status.success() {
-                return Err(anyhow!("git push failed"));
+    let templates = prompts::resolve(config.as_ref(), role.as_deref())?;
+    let llm = LlmClient::with_profile(config.as_ref(), profile.as_deref())?;
+    let diff = llm.prepare_diff(&diff).await?;
+
+    // In dry-run mode print the title and body to stdout and exit.
+    if dry_run {
+        let title = llm.chat(&templates.pr_title, &diff).await?.trim().to_string();
+        let body = llm.chat(&templates.pr_body, &diff).await?.trim().to_string();
+        println!("{}", title);
+        println!();
+        println!("{}", body);
+        return Ok(());
+    }
+
+    // Rejected title/body pairs, fed back so regenerations differ.
+    let mut rejected: Vec<String> = Vec::new();
+
+    loop {
+        let user = super::avoid(&diff, &rejected);
+
+        let sp = spinner();
+        sp.start("Generating title...");
+        let title = llm.chat(&templates.pr_title, &user).await?.trim().to_string();
+        sp.stop(format!("{} {}", style("Title:").cyan().bold(), title));
+
+        // Render the body live as the model produces it.
+        println!();
+        let body = llm
+            .chat_stream(&templates.pr_body, &user, |delta| {
+                print!("{}", style(delta).dim());
+                std::io::stdout().flush().ok();
+            })
+            .await?
+            .trim()
+            .to_string();
+        println!("\n");
+
+        let action = select("What would you like to do?")
+            .item("create", "✅ Create PR", "")
+            .item("regenerate", "🔄 Regenerate", "Ask for a different title and body")
+            .item("cancel", "✖ Cancel", "")
+            .interact()?;
+
+        match action {
+            "create" => {
+                create_pr(&base, &title, &body)?;
+                break;
+            }
+            "regenerate" => {
+                rejected.push(format!("{}\n{}", title, body));
             }
-            sp.stop(format!("{}", style("Pushed!").green()));
+            _ => break,
         }
+    }
+
+    Ok(())
+}
 
+fn create_pr(base: &str, title: &str, body: &str) -> Result<()> {
+    if !has_upstream() {
         let sp = spinner();
-        sp.start("Creating PR...");
-
-        let out = Command::new("gh")
-            .args([
-                "pr", "create", "--title", &title, "--body", &body, "--base", &base,
-            ])
-            .output()?;
-
-        if !out.status.success() {
-            return Err(anyhow!(
-                "gh pr create failed: {}",
-                String::from_utf8_lossy(&out.stderr)
-            ));
+        sp.start("Pushing to remote...");
+        let status = Command::new("git")
+            .args(["push", "-u", "origin", "HEAD"])
+            .status()?;
+        if !status.success() {
+            return Err(anyhow!("git push failed"));
         }
+        sp.stop(format!("{}", style("Pushed!").green()));
+    }
+
+    let sp = spinner();
+    sp.start("Creating PR...");
+
+    let out = Command::new("gh")
+        .args([
+            "pr", "create", "--title", title, "--body", body, "--base", base,
+        ])
+        .output()?;
 
-        let url = String::from_utf8_lossy(&out.stdout).trim().to_string();
-        sp.stop(format!("{}", style("Created!").green()));
-        log::success(format!("🔗 {}", url))?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "gh pr create failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        ));
     }
 
+    let url = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    sp.stop(format!("{}", style("Created!").green()));
+    log::success(format!("🔗 {}", url))?;
     Ok(())
 }
 
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Contents of `~/.config/git-relax/config.toml`.
+#[derive(Deserialize, Default)]
+pub struct Config {
+    /// Profile used when `--profile` is omitted.
+    pub default_profile: Option<String>,
+    /// Role used when `--role` is omitted.
+    pub default_role: Option<String>,
+    /// HTTP/HTTPS proxy URL (overridden by the `LLM_PROXY` env var).
+    pub proxy: Option<String>,
+    /// Connection timeout in seconds (default 10).
+    pub connect_timeout_secs: Option<u64>,
+    /// Print the generated message instead of acting on it. The `--dry-run`
+    /// flag can turn this on but not off; the interactive menu ignores it.
+    pub dry_run: Option<bool>,
+    /// Whether to map-reduce summarize diffs larger than the threshold
+    /// (default true).
+    pub chunk: Option<bool>,
+    /// Byte size above which a diff is summarized file-by-file, or hunk-by-hunk
+    /// when it touches a single file (default 12000).
+    pub chunk_threshold_bytes: Option<usize>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+    #[serde(default)]
+    pub roles: HashMap<String, Role>,
+}
+
+/// A named backend configuration.
+#[derive(Deserialize, Clone)]
+pub struct Profile {
+    /// `openai`, `openrouter` or `anthropic`.
+    pub provider: String,
+    pub model: String,
+    pub base_url: String,
+    /// Env var holding the API key (e.g. `ANTHROPIC_API_KEY`).
+    pub api_key_env: Option<String>,
+}
+
+/// A named set of prompt overrides; any field left unset keeps the built-in
+/// default from the `prompts` module.
+#[derive(Deserialize, Clone)]
+pub struct Role {
+    pub commit: Option<String>,
+    pub pr_title: Option<String>,
+    pub pr_body: Option<String>,
+}
+
+impl Config {
+    /// Location of the config file, honouring `$XDG_CONFIG_HOME`.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("git-relax").join("config.toml"))
+    }
+
+    /// Load the config file, returning `None` when it doesn't exist so callers
+    /// can fall back to the env-var behaviour.
+    pub fn load() -> Result<Option<Self>> {
+        let Some(path) = Self::path() else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let config =
+            toml::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        Ok(Some(config))
+    }
+
+    /// Resolve a profile by name, falling back to `default_profile`.
+    pub fn profile(&self, name: Option<&str>) -> Option<Profile> {
+        let name = name.or(self.default_profile.as_deref())?;
+        self.profiles.get(name).cloned()
+    }
+
+    /// Resolve a role by name, falling back to `default_role`.
+    pub fn role(&self, name: Option<&str>) -> Option<Role> {
+        let name = name.or(self.default_role.as_deref())?;
+        self.roles.get(name).cloned()
+    }
+
+}
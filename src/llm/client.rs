@@ -1,75 +1,80 @@
 use anyhow::{anyhow, Result};
+use eventsource_stream::Eventsource;
+use futures_util::StreamExt;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::env;
+use std::time::Duration;
 
-pub struct LlmClient {
-    client: Client,
-    api_key: String,
-    model: String,
-    base_url: String,
-}
-
-#[derive(Serialize)]
-struct ChatRequest {
-    model: String,
-    messages: Vec<Message>,
-}
-
-#[derive(Serialize)]
-struct Message {
-    role: &'static str,
-    content: String,
-}
+use super::prompts;
+use super::provider::{AnthropicProvider, OpenAiProvider, Provider};
+use crate::config::{Config, Profile};
 
-#[derive(Deserialize)]
-struct ChatResponse {
-    choices: Vec<Choice>,
-}
+/// Connection timeout used when the config file doesn't set one, so a dead
+/// network surfaces an error instead of spinning forever.
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
 
-#[derive(Deserialize)]
-struct Choice {
-    message: MessageContent,
-}
+/// Diff size above which we map-reduce summarize before the final prompt.
+const DEFAULT_CHUNK_THRESHOLD_BYTES: usize = 12_000;
 
-#[derive(Deserialize)]
-struct MessageContent {
-    content: String,
+pub struct LlmClient {
+    client: Client,
+    provider: Box<dyn Provider>,
+    chunk: bool,
+    chunk_threshold: usize,
 }
 
 impl LlmClient {
-    pub fn new() -> Result<Self> {
-        // OpenRouter (default) or OpenAI-compatible
-        let (api_key, model, base_url) = if let Ok(key) = env::var("OPENROUTER_API_KEY") {
-            (
-                key,
-                env::var("LLM_MODEL").unwrap_or_else(|_| "google/gemini-2.0-flash-001".into()),
-                "https://openrouter.ai/api/v1/chat/completions".into(),
-            )
-        } else if let Ok(key) = env::var("OPENAI_API_KEY") {
-            (
-                key,
-                env::var("LLM_MODEL").unwrap_or_else(|_| "gpt-4o-mini".into()),
-                "https://api.openai.com/v1/chat/completions".into(),
-            )
-        } else {
-            return Err(anyhow!("Set OPENROUTER_API_KEY or OPENAI_API_KEY"));
+    /// Build a client from the named profile in the (already loaded) config,
+    /// falling back to the legacy `OPENROUTER_API_KEY`/`OPENAI_API_KEY` probe
+    /// when no config is present.
+    pub fn with_profile(config: Option<&Config>, profile: Option<&str>) -> Result<Self> {
+        let client = build_http_client(config)?;
+        let chunk = config.and_then(|c| c.chunk).unwrap_or(true);
+        let chunk_threshold = config
+            .and_then(|c| c.chunk_threshold_bytes)
+            .unwrap_or(DEFAULT_CHUNK_THRESHOLD_BYTES);
+
+        let provider = match config {
+            Some(config) => match config.profile(profile) {
+                Some(p) => provider_from_profile(p)?,
+                // A profile was explicitly requested but missing — surface it
+                // rather than silently falling back to the env vars.
+                None if profile.is_some() => {
+                    return Err(anyhow!("No profile '{}' in config.toml", profile.unwrap()));
+                }
+                None => provider_from_env()?,
+            },
+            None => provider_from_env()?,
         };
 
-        Ok(Self { client: Client::new(), api_key, model, base_url })
+        Ok(Self { client, provider, chunk, chunk_threshold })
     }
 
     pub async fn chat(&self, system: &str, user: &str) -> Result<String> {
-        let resp = self.client
-            .post(&self.base_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&ChatRequest {
-                model: self.model.clone(),
-                messages: vec![
-                    Message { role: "system", content: system.into() },
-                    Message { role: "user", content: user.into() },
-                ],
-            })
+        let resp = self
+            .provider
+            .build_request(&self.client, system, user)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(anyhow!("API error: {}", resp.text().await?));
+        }
+
+        let value = resp.json().await?;
+        self.provider.parse_response(value)
+    }
+
+    /// Stream the completion, calling `on_delta` with each content token as it
+    /// arrives and returning the full message once the stream ends.
+    pub async fn chat_stream<F>(&self, system: &str, user: &str, mut on_delta: F) -> Result<String>
+    where
+        F: FnMut(&str),
+    {
+        let resp = self
+            .provider
+            .build_stream_request(&self.client, system, user)
             .send()
             .await?;
 
@@ -77,9 +82,163 @@ impl LlmClient {
             return Err(anyhow!("API error: {}", resp.text().await?));
         }
 
-        let result: ChatResponse = resp.json().await?;
-        result.choices.first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| anyhow!("No response"))
+        // `.eventsource()` buffers the raw byte stream until a full SSE event is
+        // framed, so a `data:` line split across chunks is reassembled for us.
+        let done = self.provider.done_sentinel();
+        let mut events = resp.bytes_stream().eventsource();
+        let mut full = String::new();
+
+        while let Some(event) = events.next().await {
+            let event = event?;
+            if Some(event.data.as_str()) == done {
+                break;
+            }
+
+            // Providers interleave non-JSON control frames (e.g. pings); skip
+            // anything that isn't a well-formed event payload.
+            let value: Value = match serde_json::from_str(&event.data) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            if let Some(content) = self.provider.parse_delta(&value) {
+                on_delta(&content);
+                full.push_str(&content);
+            }
+        }
+
+        Ok(full)
+    }
+
+    /// Collapse an oversized diff into per-chunk summaries so the final prompt
+    /// fits the model's context window. Small diffs (or chunking disabled) are
+    /// returned unchanged so they keep the fast path.
+    pub async fn prepare_diff(&self, diff: &str) -> Result<String> {
+        if !self.chunk || diff.len() <= self.chunk_threshold {
+            return Ok(diff.to_string());
+        }
+
+        // Prefer file boundaries; for a single oversized file (a big refactor
+        // concentrated in one path) fall back to splitting it into hunks so it
+        // can still be map-reduced rather than overflowing the context window.
+        let mut chunks = split_diff(diff);
+        if chunks.len() <= 1 {
+            chunks = split_hunks(diff);
+        }
+        if chunks.len() <= 1 {
+            eprintln!(
+                "warning: diff exceeds {} bytes but can't be split further; sending as-is",
+                self.chunk_threshold
+            );
+            return Ok(diff.to_string());
+        }
+
+        // Map: summarize each chunk independently (paths preserved in the chunk
+        // so the model can still infer a correct scope).
+        let mut summaries = String::new();
+        for chunk in chunks {
+            let summary = self.chat(prompts::SUMMARIZE_FILE, &chunk).await?;
+            summaries.push_str(summary.trim());
+            summaries.push('\n');
+        }
+        Ok(summaries)
+    }
+}
+
+/// Split a unified diff into one string per file, keyed on the `diff --git`
+/// header lines. Content before the first header (rare) is dropped.
+fn split_diff(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("diff --git") {
+            files.push(String::new());
+        }
+        if let Some(current) = files.last_mut() {
+            current.push_str(line);
+            current.push('\n');
+        }
+    }
+    files
+}
+
+/// Split a single-file diff into one string per hunk, each prefixed with the
+/// file header (`diff --git`/`index`/`---`/`+++`) so every chunk still carries
+/// the path.
+fn split_hunks(diff: &str) -> Vec<String> {
+    let mut header = String::new();
+    let mut hunks: Vec<String> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            hunks.push(header.clone());
+        }
+        match hunks.last_mut() {
+            // Still inside the file header, before the first hunk.
+            None => {
+                header.push_str(line);
+                header.push('\n');
+            }
+            Some(current) => {
+                current.push_str(line);
+                current.push('\n');
+            }
+        }
+    }
+    hunks
+}
+
+fn build_http_client(config: Option<&Config>) -> Result<Client> {
+    let mut builder = Client::builder();
+
+    // The `LLM_PROXY` env var wins over the config file so it can be toggled
+    // per-invocation behind a corporate proxy.
+    let proxy = env::var("LLM_PROXY")
+        .ok()
+        .or_else(|| config.and_then(|c| c.proxy.clone()));
+    if let Some(url) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(url)?);
+    }
+
+    let timeout = config
+        .and_then(|c| c.connect_timeout_secs)
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+    builder = builder.connect_timeout(Duration::from_secs(timeout));
+
+    Ok(builder.build()?)
+}
+
+fn provider_from_profile(profile: Profile) -> Result<Box<dyn Provider>> {
+    let api_key = match &profile.api_key_env {
+        Some(var) => env::var(var).map_err(|_| anyhow!("env var {} not set", var))?,
+        None => String::new(),
+    };
+    match profile.provider.as_str() {
+        "openai" | "openrouter" => Ok(Box::new(OpenAiProvider {
+            api_key,
+            model: profile.model,
+            base_url: profile.base_url,
+        })),
+        "anthropic" => Ok(Box::new(AnthropicProvider {
+            api_key,
+            model: profile.model,
+            base_url: profile.base_url,
+        })),
+        other => Err(anyhow!("Unknown provider '{}'", other)),
+    }
+}
+
+fn provider_from_env() -> Result<Box<dyn Provider>> {
+    if let Ok(api_key) = env::var("OPENROUTER_API_KEY") {
+        Ok(Box::new(OpenAiProvider {
+            api_key,
+            model: env::var("LLM_MODEL").unwrap_or_else(|_| "google/gemini-2.0-flash-001".into()),
+            base_url: "https://openrouter.ai/api/v1/chat/completions".into(),
+        }))
+    } else if let Ok(api_key) = env::var("OPENAI_API_KEY") {
+        Ok(Box::new(OpenAiProvider {
+            api_key,
+            model: env::var("LLM_MODEL").unwrap_or_else(|_| "gpt-4o-mini".into()),
+            base_url: "https://api.openai.com/v1/chat/completions".into(),
+        }))
+    } else {
+        Err(anyhow!("Set OPENROUTER_API_KEY or OPENAI_API_KEY"))
     }
 }
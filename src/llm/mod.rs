@@ -0,0 +1,5 @@
+pub mod client;
+pub mod prompts;
+pub mod provider;
+
+pub use client::LlmClient;
@@ -1,3 +1,7 @@
+use anyhow::{anyhow, Result};
+
+use crate::config::Config;
+
 pub const COMMIT: &str = r#"Generate a commit message from this diff.
 Format: <type>(<scope>): <description>
 Types: feat|fix|docs|refactor|test|chore
@@ -10,6 +14,10 @@ Types: feat|fix|docs|refactor|test|chore
 Rules: lowercase, imperative mood, max 50 chars
 Output ONLY the title."#;
 
+pub const SUMMARIZE_FILE: &str = r#"Summarize the change to this file in one line.
+Start with the file path, then describe what changed.
+Output ONLY the summary line."#;
+
 pub const PR_BODY: &str = r#"Generate a PR description from this diff.
 Format:
 ## Summary
@@ -19,3 +27,39 @@ Format:
 <bullet points>
 
 Be concise. Output ONLY the description."#;
+
+/// Prompts resolved against a config role, with the constants above as
+/// built-in defaults for any field the role doesn't override.
+pub struct Templates {
+    pub commit: String,
+    pub pr_title: String,
+    pub pr_body: String,
+}
+
+/// Resolve the prompt set for a role, falling back to the config's
+/// `default_role` and then to the built-in constants. Errors when a role is
+/// named explicitly but not found.
+pub fn resolve(config: Option<&Config>, role: Option<&str>) -> Result<Templates> {
+    let resolved = match config.map(|c| c.role(role)) {
+        Some(Some(r)) => Some(r),
+        _ if role.is_some() => {
+            return Err(anyhow!("No role '{}' in config.toml", role.unwrap()));
+        }
+        _ => None,
+    };
+
+    Ok(Templates {
+        commit: resolved
+            .as_ref()
+            .and_then(|r| r.commit.clone())
+            .unwrap_or_else(|| COMMIT.to_string()),
+        pr_title: resolved
+            .as_ref()
+            .and_then(|r| r.pr_title.clone())
+            .unwrap_or_else(|| PR_TITLE.to_string()),
+        pr_body: resolved
+            .as_ref()
+            .and_then(|r| r.pr_body.clone())
+            .unwrap_or_else(|| PR_BODY.to_string()),
+    })
+}
@@ -0,0 +1,141 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+
+/// A chat backend. Each provider knows how to frame a request for its own API
+/// shape and how to pull the assistant's text back out of the response.
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// Build a non-streaming request carrying the system + user prompt.
+    fn build_request(&self, client: &Client, system: &str, user: &str) -> RequestBuilder;
+
+    /// Extract the assistant text from a successful JSON response body.
+    fn parse_response(&self, value: Value) -> Result<String>;
+
+    /// Build the streaming (SSE) variant of the request.
+    fn build_stream_request(&self, client: &Client, system: &str, user: &str) -> RequestBuilder;
+
+    /// Pull a content delta out of a single parsed SSE event, if it carries one.
+    fn parse_delta(&self, value: &Value) -> Option<String>;
+
+    /// The `data:` payload that marks the end of the stream, if the provider
+    /// emits one (OpenAI sends `[DONE]`; Anthropic just closes the stream).
+    fn done_sentinel(&self) -> Option<&str> {
+        Some("[DONE]")
+    }
+}
+
+/// OpenAI chat-completions shape, also spoken by OpenRouter, Ollama and most
+/// gateways.
+pub struct OpenAiProvider {
+    pub api_key: String,
+    pub model: String,
+    pub base_url: String,
+}
+
+#[async_trait]
+impl Provider for OpenAiProvider {
+    fn build_request(&self, client: &Client, system: &str, user: &str) -> RequestBuilder {
+        client
+            .post(&self.base_url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&json!({
+                "model": self.model,
+                "messages": [
+                    {"role": "system", "content": system},
+                    {"role": "user", "content": user},
+                ],
+            }))
+    }
+
+    fn parse_response(&self, value: Value) -> Result<String> {
+        value["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("No response"))
+    }
+
+    fn build_stream_request(&self, client: &Client, system: &str, user: &str) -> RequestBuilder {
+        client
+            .post(&self.base_url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&json!({
+                "model": self.model,
+                "messages": [
+                    {"role": "system", "content": system},
+                    {"role": "user", "content": user},
+                ],
+                "stream": true,
+            }))
+    }
+
+    fn parse_delta(&self, value: &Value) -> Option<String> {
+        value["choices"][0]["delta"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+    }
+}
+
+/// Anthropic Messages API: `system` is top-level, messages carry only
+/// user/assistant turns, and the text lives under `content[0].text`.
+pub struct AnthropicProvider {
+    pub api_key: String,
+    pub model: String,
+    pub base_url: String,
+}
+
+impl AnthropicProvider {
+    const MAX_TOKENS: u32 = 1024;
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    fn build_request(&self, client: &Client, system: &str, user: &str) -> RequestBuilder {
+        client
+            .post(&self.base_url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&json!({
+                "model": self.model,
+                "max_tokens": Self::MAX_TOKENS,
+                "system": system,
+                "messages": [
+                    {"role": "user", "content": user},
+                ],
+            }))
+    }
+
+    fn parse_response(&self, value: Value) -> Result<String> {
+        value["content"][0]["text"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("No response"))
+    }
+
+    fn build_stream_request(&self, client: &Client, system: &str, user: &str) -> RequestBuilder {
+        client
+            .post(&self.base_url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&json!({
+                "model": self.model,
+                "max_tokens": Self::MAX_TOKENS,
+                "system": system,
+                "messages": [
+                    {"role": "user", "content": user},
+                ],
+                "stream": true,
+            }))
+    }
+
+    fn parse_delta(&self, value: &Value) -> Option<String> {
+        // `content_block_delta` events carry `delta.text`; other event types
+        // (message_start, ping, …) have no text and are skipped.
+        value["delta"]["text"].as_str().map(|s| s.to_string())
+    }
+
+    fn done_sentinel(&self) -> Option<&str> {
+        None
+    }
+}
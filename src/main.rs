@@ -2,7 +2,10 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::env;
 
+use config::Config;
+
 mod commands;
+mod config;
 mod llm;
 
 #[derive(Parser)]
@@ -14,12 +17,36 @@ mod llm;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Config profile to use (see ~/.config/git-relax/config.toml); ignored by the interactive menu
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Prompt role to use (see [roles] in config.toml); ignored by the interactive menu
+    #[arg(long, global = true)]
+    role: Option<String>,
+
+    /// Print the generated message without committing or calling gh; ignored by the interactive menu
+    #[arg(long, global = true)]
+    dry_run: bool,
 }
 
 #[derive(Parser)]
 #[command(name = "grlcm")]
 #[command(about = "🧘 AI-powered commit message generator")]
-struct GrlcmCli;
+struct GrlcmCli {
+    /// Config profile to use (see ~/.config/git-relax/config.toml)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Prompt role to use (see [roles] in config.toml)
+    #[arg(long)]
+    role: Option<String>,
+
+    /// Print the generated message without committing
+    #[arg(long)]
+    dry_run: bool,
+}
 
 #[derive(Parser)]
 #[command(name = "grlpr")]
@@ -28,6 +55,18 @@ struct GrlprCli {
     /// Base branch (default: main)
     #[arg(short, long)]
     base: Option<String>,
+
+    /// Config profile to use (see ~/.config/git-relax/config.toml)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Prompt role to use (see [roles] in config.toml)
+    #[arg(long)]
+    role: Option<String>,
+
+    /// Print the generated PR title and body without calling gh
+    #[arg(long)]
+    dry_run: bool,
 }
 
 #[derive(Subcommand)]
@@ -58,19 +97,38 @@ async fn main() -> Result<()> {
 
     let bin_name = get_binary_name();
 
+    // Read the config file exactly once and thread it through the commands.
+    let config = Config::load()?;
+    let dry_run_default = config.as_ref().and_then(|c| c.dry_run).unwrap_or(false);
+
     match bin_name.as_str() {
         "grlcm" => {
-            GrlcmCli::parse();
-            commands::cm::run().await
+            let cli = GrlcmCli::parse();
+            commands::cm::run(config, cli.profile, cli.role, cli.dry_run || dry_run_default).await
         }
         "grlpr" => {
             let cli = GrlprCli::parse();
-            commands::pr::run(cli.base).await
+            commands::pr::run(
+                config,
+                cli.base,
+                cli.profile,
+                cli.role,
+                cli.dry_run || dry_run_default,
+            )
+            .await
+        }
+        _ => {
+            let cli = Cli::parse();
+            let dry_run = cli.dry_run || dry_run_default;
+            match cli.command {
+                Some(Commands::Commit) => {
+                    commands::cm::run(config, cli.profile, cli.role, dry_run).await
+                }
+                Some(Commands::Pull { base }) => {
+                    commands::pr::run(config, base, cli.profile, cli.role, dry_run).await
+                }
+                None => commands::interactive::run(config).await,
+            }
         }
-        _ => match Cli::parse().command {
-            Some(Commands::Commit) => commands::cm::run().await,
-            Some(Commands::Pull { base }) => commands::pr::run(base).await,
-            None => commands::interactive::run().await,
-        },
     }
 }